@@ -1,11 +1,151 @@
+// pyo3's #[pymethods]/#[pyfunction] expansion wraps every return value in a
+// `PyResult` conversion that clippy can't see through; allow it crate-wide
+// rather than peppering every method with a local allow.
+#![allow(clippy::useless_conversion)]
+
+use numpy::ndarray::{Array2, Array4};
+use numpy::{IntoPyArray, PyArray2, PyArray4};
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Columns excluded when shifting east/west so runs don't wrap across rows.
+const NOT_A_FILE: u64 = 0xfefe_fefe_fefe_fefe; // all columns except column 0 (the A-file)
+const NOT_H_FILE: u64 = 0x7f7f_7f7f_7f7f_7f7f; // all columns except column 7 (the H-file)
+
+fn shift_e(b: u64) -> u64 {
+    (b & NOT_H_FILE) << 1
+}
+fn shift_w(b: u64) -> u64 {
+    (b & NOT_A_FILE) >> 1
+}
+fn shift_n(b: u64) -> u64 {
+    b >> 8
+}
+fn shift_s(b: u64) -> u64 {
+    b << 8
+}
+fn shift_ne(b: u64) -> u64 {
+    (b & NOT_H_FILE) >> 7
+}
+fn shift_nw(b: u64) -> u64 {
+    (b & NOT_A_FILE) >> 9
+}
+fn shift_se(b: u64) -> u64 {
+    (b & NOT_H_FILE) << 9
+}
+fn shift_sw(b: u64) -> u64 {
+    (b & NOT_A_FILE) << 7
+}
+
+/// The eight ray directions, each a shift function with its wrap mask baked in.
+const DIRECTIONS: [fn(u64) -> u64; 8] = [
+    shift_e, shift_w, shift_n, shift_s, shift_ne, shift_nw, shift_se, shift_sw,
+];
+
+/// Bitmask of legal moves for `own` to play against `opp`.
+///
+/// For each direction, accumulate a run of opponent stones reachable from
+/// `own` by repeated shifting, then a move is legal if shifting once more
+/// from the end of that run lands on an empty square.
+fn legal_mask(own: u64, opp: u64) -> u64 {
+    let empty = !(own | opp);
+    let mut moves = 0u64;
+    for shift in DIRECTIONS {
+        let mut t = shift(own) & opp;
+        for _ in 0..5 {
+            t |= shift(t) & opp;
+        }
+        moves |= shift(t) & empty;
+    }
+    moves
+}
+
+/// Bitmask of opponent stones flipped by playing `own` at `idx`.
+fn flips_for_move(idx: usize, own: u64, opp: u64) -> u64 {
+    let placed = 1u64 << idx;
+    let mut flips = 0u64;
+    for shift in DIRECTIONS {
+        let mut t = shift(placed) & opp;
+        for _ in 0..5 {
+            t |= shift(t) & opp;
+        }
+        if shift(t) & own != 0 {
+            flips |= t;
+        }
+    }
+    flips
+}
+
+/// Keys for incremental Zobrist hashing: one per (colour, square), plus one
+/// for side-to-move. Seeded deterministically so hashes are stable across runs.
+struct ZobristTable {
+    squares: [[u64; 64]; 2], // [0] = black, [1] = white
+    side: u64,
+}
+
+fn zobrist() -> &'static ZobristTable {
+    static TABLE: std::sync::OnceLock<ZobristTable> = std::sync::OnceLock::new();
+    TABLE.get_or_init(|| {
+        // xorshift64, fixed seed: reproducible across runs and platforms.
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut squares = [[0u64; 64]; 2];
+        for colour in squares.iter_mut() {
+            for key in colour.iter_mut() {
+                *key = next();
+            }
+        }
+        ZobristTable {
+            squares,
+            side: next(),
+        }
+    })
+}
+
+/// Hash for an arbitrary (own, opp, black_to_move) board state, computed from scratch.
+fn hash_for(own: u64, opp: u64, black_to_move: bool) -> u64 {
+    let table = zobrist();
+    let (black, white) = if black_to_move {
+        (own, opp)
+    } else {
+        (opp, own)
+    };
+    let mut hash = 0u64;
+    let mut b = black;
+    while b != 0 {
+        let i = b.trailing_zeros() as usize;
+        hash ^= table.squares[0][i];
+        b &= b - 1;
+    }
+    let mut w = white;
+    while w != 0 {
+        let i = w.trailing_zeros() as usize;
+        hash ^= table.squares[1][i];
+        w &= w - 1;
+    }
+    if !black_to_move {
+        hash ^= table.side;
+    }
+    hash
+}
 
 /// Internal stone representation (0 = empty, 1 = black, -1 = white).
 #[pyclass]
 #[derive(Clone)]
 pub struct Board {
-    cells: [i8; 64],
+    // `own`/`opp` always hold the stones of the side to move / the other
+    // side; `black_to_move` says which colour `own` currently is.
+    own: u64,
+    opp: u64,
     black_to_move: bool,
+    hash: u64,
+    // Snapshots of (own, opp, black_to_move, hash) for `undo`.
+    history: Vec<(u64, u64, bool, u64)>,
 }
 
 #[pymethods]
@@ -13,15 +153,16 @@ impl Board {
     /// Create the initial Othello position.
     #[new]
     pub fn new() -> Self {
-        let mut cells = [0i8; 64];
-        // central four stones
-        cells[27] = -1;
-        cells[28] = 1;
-        cells[35] = 1;
-        cells[36] = -1;
+        // Black (own, to move first) at d5/e4, white (opp) at d4/e5.
+        let own = (1u64 << 28) | (1u64 << 35);
+        let opp = (1u64 << 27) | (1u64 << 36);
+        let black_to_move = true;
         Self {
-            cells,
-            black_to_move: true,
+            own,
+            opp,
+            black_to_move,
+            hash: hash_for(own, opp, black_to_move),
+            history: Vec::new(),
         }
     }
 
@@ -37,119 +178,327 @@ impl Board {
 
     /// Return true if idx (0‑63) is a legal move for current side.
     pub fn is_legal(&self, idx: usize) -> bool {
-        if self.cells[idx] != 0 {
-            return false;
-        }
-        const DIRS: [i8; 8] = [-9, -8, -7, -1, 1, 7, 8, 9];
-        let my = if self.black_to_move { 1 } else { -1 };
-        let opp = -my;
-        let _r = idx as i8 / 8;
-        let c = idx as i8 % 8;
-        for d in DIRS {
-            // step in each direction
-            let mut x = idx as i8 + d;
-            let mut cnt = 0;
-            while (0..64).contains(&x) {
-                let _xr = x / 8;
-                let xc = x % 8;
-                // board wrap check
-                if (d == -1 || d == 7 || d == -9) && xc > c {
-                    break;
-                }
-                if (d == 1 || d == -7 || d == 9) && xc < c {
-                    break;
-                }
-                let s = self.cells[x as usize];
-                if s == opp {
-                    cnt += 1;
-                } else if s == my {
-                    if cnt > 0 {
-                        return true;
-                    } else {
-                        break;
-                    }
-                } else {
-                    break;
-                }
-                x += d;
-            }
-        }
-        false
+        legal_mask(self.own, self.opp) & (1u64 << idx) != 0
     }
 
     /// Vector of all legal move indices for current side.
     pub fn legal_moves(&self) -> Vec<usize> {
-        (0..64).filter(|&i| self.is_legal(i)).collect()
+        let mut mask = legal_mask(self.own, self.opp);
+        let mut out = Vec::with_capacity(mask.count_ones() as usize);
+        while mask != 0 {
+            let idx = mask.trailing_zeros() as usize;
+            out.push(idx);
+            mask &= mask - 1;
+        }
+        out
     }
 
     /// Play a move; returns number of stones flipped, or Err if illegal.
+    ///
+    /// Automatically skips the turn back to this side if, after the move,
+    /// the other side has no legal response (but this side still does) —
+    /// callers only need to handle the true end of game via [`Board::is_terminal`].
     pub fn play(&mut self, idx: usize) -> PyResult<usize> {
         if !self.is_legal(idx) {
             return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
                 "Illegal move",
             ));
         }
-        const DIRS: [i8; 8] = [-9, -8, -7, -1, 1, 7, 8, 9];
-        let my = if self.black_to_move { 1 } else { -1 };
-        let opp = -my;
-        let _r = idx as i8 / 8;
-        let c = idx as i8 % 8;
-        let mut flipped = 0;
-        self.cells[idx] = my;
-        for d in DIRS {
-            let mut buf: Vec<usize> = Vec::new();
-            let mut x = idx as i8 + d;
-            while (0..64).contains(&x) {
-                let _xr = x / 8;
-                let xc = x % 8;
-                if (d == -1 || d == 7 || d == -9) && xc > c {
-                    break;
-                }
-                if (d == 1 || d == -7 || d == 9) && xc < c {
-                    break;
-                }
-                let s = self.cells[x as usize];
-                if s == opp {
-                    buf.push(x as usize);
-                } else if s == my {
-                    for i in &buf {
-                        self.cells[*i] = my;
-                    }
-                    flipped += buf.len();
-                    break;
-                } else {
-                    break;
-                }
-                x += d;
-            }
+        self.history
+            .push((self.own, self.opp, self.black_to_move, self.hash));
+
+        let flips = flips_for_move(idx, self.own, self.opp);
+
+        let table = zobrist();
+        let mover_color = if self.black_to_move { 0 } else { 1 };
+        let opp_color = 1 - mover_color;
+        self.hash ^= table.squares[mover_color][idx];
+        let mut f = flips;
+        while f != 0 {
+            let i = f.trailing_zeros() as usize;
+            self.hash ^= table.squares[opp_color][i] ^ table.squares[mover_color][i];
+            f &= f - 1;
         }
+        self.hash ^= table.side;
+
+        let new_own = self.opp & !flips;
+        let new_opp = self.own | flips | (1u64 << idx);
+        self.own = new_own;
+        self.opp = new_opp;
         self.black_to_move = !self.black_to_move;
-        Ok(flipped as usize)
+        if !self.has_any_move() && legal_mask(self.opp, self.own) != 0 {
+            self.swap_turn();
+        }
+        Ok(flips.count_ones() as usize)
     }
 
-    /// Return (black, white) counts.
-    pub fn counts(&self) -> (u8, u8) {
-        let mut b = 0u8;
-        let mut w = 0u8;
-        for &c in &self.cells {
-            if c == 1 {
-                b += 1;
-            } else if c == -1 {
-                w += 1;
+    /// Current Zobrist hash of the position (including side to move).
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Undo the last `play`/`pass`, restoring cells, side to move, and hash.
+    pub fn undo(&mut self) -> PyResult<()> {
+        match self.history.pop() {
+            Some((own, opp, black_to_move, hash)) => {
+                self.own = own;
+                self.opp = opp;
+                self.black_to_move = black_to_move;
+                self.hash = hash;
+                Ok(())
             }
+            None => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "No move to undo",
+            )),
         }
-        (b, w)
+    }
+
+    /// Return true if the side to move has at least one legal move.
+    pub fn has_any_move(&self) -> bool {
+        legal_mask(self.own, self.opp) != 0
+    }
+
+    /// Pass the turn; errors if the current side actually has a legal move.
+    pub fn pass(&mut self) -> PyResult<()> {
+        if self.has_any_move() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Cannot pass: a legal move exists",
+            ));
+        }
+        self.history
+            .push((self.own, self.opp, self.black_to_move, self.hash));
+        self.swap_turn();
+        Ok(())
+    }
+
+    /// True once neither side has a legal move and the game is over.
+    pub fn is_terminal(&self) -> bool {
+        !self.has_any_move() && legal_mask(self.opp, self.own) == 0
+    }
+
+    /// Winner once terminal: `Some(1)` black, `Some(-1)` white, `Some(0)` draw, else `None`.
+    pub fn winner(&self) -> Option<i8> {
+        if !self.is_terminal() {
+            return None;
+        }
+        let (black, white) = self.counts();
+        Some(match black.cmp(&white) {
+            std::cmp::Ordering::Greater => 1,
+            std::cmp::Ordering::Less => -1,
+            std::cmp::Ordering::Equal => 0,
+        })
+    }
+
+    /// Return (black, white) counts.
+    pub fn counts(&self) -> (u8, u8) {
+        let (black, white) = if self.black_to_move {
+            (self.own, self.opp)
+        } else {
+            (self.opp, self.own)
+        };
+        (black.count_ones() as u8, white.count_ones() as u8)
     }
 
     /// Python helper to get simple list for observation.
     pub fn as_list(&self) -> Vec<i8> {
-        self.cells.to_vec()
+        let (black, white) = if self.black_to_move {
+            (self.own, self.opp)
+        } else {
+            (self.opp, self.own)
+        };
+        (0..64)
+            .map(|i| {
+                let bit = 1u64 << i;
+                if black & bit != 0 {
+                    1
+                } else if white & bit != 0 {
+                    -1
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+impl Board {
+    /// Hand the turn to the other side without changing any stones.
+    fn swap_turn(&mut self) {
+        std::mem::swap(&mut self.own, &mut self.opp);
+        self.black_to_move = !self.black_to_move;
+        self.hash ^= zobrist().side;
+    }
+
+    /// Build a board directly from bitboards, for tests that probe arbitrary
+    /// (not necessarily reachable) positions.
+    #[cfg(test)]
+    fn from_raw(own: u64, opp: u64, black_to_move: bool) -> Self {
+        Self {
+            own,
+            opp,
+            black_to_move,
+            hash: hash_for(own, opp, black_to_move),
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Flatten a bitboard into a 64-element 0/1 plane in square order.
+fn bits_to_plane(bits: u64) -> [i8; 64] {
+    let mut plane = [0i8; 64];
+    for (i, cell) in plane.iter_mut().enumerate() {
+        *cell = ((bits >> i) & 1) as i8;
+    }
+    plane
+}
+
+/// Gym-style self-play environment wrapping a [`Board`].
+///
+/// The observation is three 8×8 planes (own stones, opponent stones, legal
+/// moves) flattened in that order, always from the perspective of the side
+/// to move.
+#[pyclass]
+pub struct ReversiEnv {
+    board: Board,
+}
+
+#[pymethods]
+impl ReversiEnv {
+    #[new]
+    pub fn new() -> Self {
+        Self {
+            board: Board::new(),
+        }
+    }
+
+    /// Reset the environment and return the initial observation.
+    pub fn reset(&mut self) -> Vec<i8> {
+        self.board.reset();
+        self.observation()
+    }
+
+    /// Three-plane (own, opponent, legal-move) observation for the side to move.
+    pub fn observation(&self) -> Vec<i8> {
+        let legal = legal_mask(self.board.own, self.board.opp);
+        let mut obs = Vec::with_capacity(192);
+        obs.extend_from_slice(&bits_to_plane(self.board.own));
+        obs.extend_from_slice(&bits_to_plane(self.board.opp));
+        obs.extend_from_slice(&bits_to_plane(legal));
+        obs
+    }
+
+    /// Boolean mask over the 64 actions, true where the move is legal.
+    pub fn action_mask(&self) -> Vec<bool> {
+        let legal = legal_mask(self.board.own, self.board.opp);
+        (0..64).map(|i| legal & (1u64 << i) != 0).collect()
+    }
+
+    /// Apply `action`, returning `(observation, reward, done, info)`.
+    ///
+    /// `reward` is 0 until the game ends, then ±1/0 from the perspective of
+    /// the side that just moved. Passes for a side with no legal response
+    /// are handled automatically by [`Board::play`]; the caller only ever
+    /// supplies moves for a side that can actually move.
+    pub fn step(
+        &mut self,
+        py: Python<'_>,
+        action: usize,
+    ) -> PyResult<(Vec<i8>, f32, bool, PyObject)> {
+        let mover_was_black = self.board.get_black_to_move();
+        self.board.play(action)?;
+        let done = self.board.is_terminal();
+        let reward = match (done, self.board.winner()) {
+            (true, Some(w)) => {
+                if mover_was_black {
+                    w as f32
+                } else {
+                    -w as f32
+                }
+            }
+            _ => 0.0,
+        };
+        let info = PyDict::new_bound(py);
+        info.set_item("legal_moves", self.board.legal_moves())?;
+        Ok((self.observation(), reward, done, info.into()))
+    }
+}
+
+impl Default for ReversiEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The same three (own, opponent, legal-move) planes as
+/// [`ReversiEnv::observation`], for one board. With `canonical` (the
+/// default), "own"/"opponent" follow the side to move, matching
+/// `ReversiEnv` exactly; otherwise they're always black/white regardless of
+/// whose turn it is.
+fn encode_one(board: &Board, canonical: bool) -> [i8; 3 * 64] {
+    let (own, opp) = if canonical || board.black_to_move {
+        (board.own, board.opp)
+    } else {
+        (board.opp, board.own)
+    };
+    let legal = legal_mask(board.own, board.opp);
+    let mut planes = [0i8; 3 * 64];
+    planes[0..64].copy_from_slice(&bits_to_plane(own));
+    planes[64..128].copy_from_slice(&bits_to_plane(opp));
+    planes[128..192].copy_from_slice(&bits_to_plane(legal));
+    planes
+}
+
+/// Encode a batch of boards as a contiguous `(N, 3, 8, 8)` array: the same
+/// (own, opponent, legal-move) planes as [`ReversiEnv::observation`], so a
+/// net trained on one can be fed from the other. With `canonical` (the
+/// default), the two stone planes are swapped so the side to move is always "own",
+/// matching `ReversiEnv`; otherwise planes are always black/white regardless
+/// of whose turn it is. Doing this in one call avoids per-board Python call
+/// overhead when feeding a training batch.
+#[pyfunction]
+#[pyo3(signature = (boards, canonical=true))]
+fn encode_batch(py: Python<'_>, boards: Vec<Board>, canonical: bool) -> Py<PyArray4<i8>> {
+    let n = boards.len();
+    let mut data = vec![0i8; n * 3 * 64];
+    for (b, board) in boards.iter().enumerate() {
+        let base = b * 3 * 64;
+        data[base..base + 3 * 64].copy_from_slice(&encode_one(board, canonical));
+    }
+    Array4::from_shape_vec((n, 3, 8, 8), data)
+        .expect("buffer length matches shape")
+        .into_pyarray_bound(py)
+        .unbind()
+}
+
+/// Legal-move mask for a batch of boards, as an `(N, 64)` boolean array.
+#[pyfunction]
+fn legal_masks_batch(py: Python<'_>, boards: Vec<Board>) -> Py<PyArray2<bool>> {
+    let n = boards.len();
+    let mut data = vec![false; n * 64];
+    for (b, board) in boards.iter().enumerate() {
+        let mask = legal_mask(board.own, board.opp);
+        for i in 0..64 {
+            data[b * 64 + i] = (mask >> i) & 1 != 0;
+        }
+    }
+    Array2::from_shape_vec((n, 64), data)
+        .expect("buffer length matches shape")
+        .into_pyarray_bound(py)
+        .unbind()
+}
+
 #[pymodule]
 fn reversi_rl(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Board>()?;
+    m.add_class::<ReversiEnv>()?;
+    m.add_function(wrap_pyfunction!(encode_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(legal_masks_batch, m)?)?;
     Ok(())
 }
 
@@ -157,6 +506,81 @@ fn reversi_rl(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Deterministic xorshift64 PRNG so tests are reproducible without a `rand` dependency.
+    struct Rng(u64);
+    impl Rng {
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    /// A from-scratch O(64·8) per-square legal-move check, kept here only as
+    /// an oracle to validate the bitboard generator against. Walks each ray
+    /// in (row, col) space so it can't misjudge A/H-file wrap the way a
+    /// modular-arithmetic column check can from an edge-file origin square.
+    fn naive_legal_moves(cells: &[i8; 64], black_to_move: bool) -> Vec<usize> {
+        const DIRS: [(i8, i8); 8] = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+        let my = if black_to_move { 1 } else { -1 };
+        let opp = -my;
+        let mut out = Vec::new();
+        for idx in 0..64 {
+            if cells[idx] != 0 {
+                continue;
+            }
+            let row = (idx / 8) as i8;
+            let col = (idx % 8) as i8;
+            let mut legal = false;
+            for (dr, dc) in DIRS {
+                let mut r = row + dr;
+                let mut c = col + dc;
+                let mut cnt = 0;
+                while (0..8).contains(&r) && (0..8).contains(&c) {
+                    let s = cells[(r * 8 + c) as usize];
+                    if s == opp {
+                        cnt += 1;
+                    } else if s == my {
+                        if cnt > 0 {
+                            legal = true;
+                        }
+                        break;
+                    } else {
+                        break;
+                    }
+                    r += dr;
+                    c += dc;
+                }
+                if legal {
+                    break;
+                }
+            }
+            if legal {
+                out.push(idx);
+            }
+        }
+        out
+    }
+
+    fn cells_from_board(b: &Board) -> [i8; 64] {
+        let mut cells = [0i8; 64];
+        for (i, c) in cells.iter_mut().enumerate() {
+            *c = b.as_list()[i];
+        }
+        cells
+    }
+
     #[test]
     fn initial_legal_moves() {
         let b = Board::new();
@@ -175,4 +599,89 @@ mod tests {
         let (black, white) = b.counts();
         assert_eq!((black, white), (4, 1));
     }
+
+    #[test]
+    fn bitboard_matches_naive_on_random_positions() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        for _ in 0..20_000 {
+            // Scatter stones randomly (not necessarily reachable game states);
+            // the generator must agree with the naive oracle regardless.
+            let own = rng.next_u64();
+            let opp = rng.next_u64() & !own;
+            let black_to_move = rng.next_u64().is_multiple_of(2);
+            let b = Board::from_raw(own, opp, black_to_move);
+            let cells = cells_from_board(&b);
+            let mut expected = naive_legal_moves(&cells, black_to_move);
+            let mut actual = b.legal_moves();
+            expected.sort_unstable();
+            actual.sort_unstable();
+            assert_eq!(expected, actual, "own={own:#x} opp={opp:#x}");
+        }
+    }
+
+    #[test]
+    fn pass_errors_when_a_legal_move_exists() {
+        let mut b = Board::new();
+        assert!(b.pass().is_err());
+    }
+
+    #[test]
+    fn pass_swaps_turn_when_no_legal_move() {
+        // White (own) to move with no stones of its own, so it has no legal
+        // move; black holds a single stone on square 0.
+        let mut b = Board::from_raw(0, 1u64 << 0, false);
+        assert!(!b.has_any_move());
+        b.pass().unwrap();
+        assert!(b.get_black_to_move());
+    }
+
+    #[test]
+    fn is_terminal_true_when_board_is_full() {
+        let b = Board::from_raw(0xffff_ffff_0000_0000, 0x0000_0000_ffff_ffff, true);
+        assert!(b.is_terminal());
+        assert_eq!(b.winner(), Some(0));
+    }
+
+    #[test]
+    fn winner_is_none_before_terminal() {
+        let b = Board::new();
+        assert_eq!(b.winner(), None);
+    }
+
+    #[test]
+    fn play_then_undo_restores_board_and_hash() {
+        let mut rng = Rng(0xdeadbeefcafef00d);
+        let mut b = Board::new();
+        let original_own = b.own;
+        let original_opp = b.opp;
+        let original_black_to_move = b.black_to_move;
+        let original_hash = b.hash;
+
+        let mut played = 0;
+        while played < 12 && !b.is_terminal() {
+            let moves = b.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            let mv = moves[(rng.next_u64() as usize) % moves.len()];
+            b.play(mv).unwrap();
+            played += 1;
+        }
+
+        for _ in 0..played {
+            b.undo().unwrap();
+        }
+
+        assert_eq!(b.own, original_own);
+        assert_eq!(b.opp, original_opp);
+        assert_eq!(b.black_to_move, original_black_to_move);
+        assert_eq!(b.hash, original_hash);
+        assert!(b.undo().is_err());
+    }
+
+    #[test]
+    fn hash_matches_from_scratch_recomputation() {
+        let b = Board::new();
+        assert_eq!(b.hash(), hash_for(b.own, b.opp, b.black_to_move));
+    }
 }